@@ -1,15 +1,16 @@
-use std::{num::NonZeroUsize, sync::Arc};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use dozer_log::{
     camino::{Utf8Path, Utf8PathBuf},
     dyn_clone,
+    futures_util::stream::{FuturesOrdered, StreamExt},
     replication::create_data_storage,
     storage::{self, Object, Queue, Storage},
-    tokio::{sync::mpsc::error::SendError, task::JoinHandle},
+    tokio::{self, sync::mpsc::error::SendError, task::JoinHandle},
 };
 use dozer_types::{
     bincode,
-    log::{error, info},
+    log::{error, info, warn},
     models::app_config::DataStorage,
     node::{NodeHandle, OpIdentifier, SourceStates},
     parking_lot::Mutex,
@@ -20,12 +21,18 @@ use tempdir::TempDir;
 
 use crate::{errors::ExecutionError, processor_record::ProcessorRecordStore};
 
+mod registry;
+
+pub use registry::{CheckpointRegistry, CommittedCheckpoint};
+
 #[derive(Debug)]
 pub struct CheckpointFactory {
     queue: Queue,
     storage: Box<dyn Storage>, // only used in test now
     prefix: String,
     record_store: Arc<ProcessorRecordStore>,
+    compaction_slice_threshold: usize,
+    registry: Option<Arc<CheckpointRegistry>>,
     state: Mutex<CheckpointWriterFactoryState>,
 }
 
@@ -33,6 +40,21 @@ pub struct CheckpointFactory {
 pub struct CheckpointFactoryOptions {
     pub storage_config: DataStorage,
     pub persist_queue_capacity: usize,
+    /// Number of `download_object` calls to keep in flight while restoring the
+    /// record store from `record_store/` slices. Higher values hide per-object
+    /// round-trip latency on high-latency backends like S3, at the cost of more
+    /// concurrent downloads buffered in memory.
+    pub restore_concurrency: usize,
+    /// Number of `record_store/` slices to tolerate before a background task
+    /// consolidates them into a single object, bounding restore cost.
+    pub compaction_slice_threshold: usize,
+    /// Postgres connection string for the optional queryable checkpoint registry.
+    /// When set, every committed epoch is additionally upserted into a
+    /// `checkpoints` table so progress can be queried without listing storage.
+    pub registry_database_url: Option<String>,
+    /// How often to touch the registry's `updated_at` column even when no new
+    /// epoch has committed, so a stalled pipeline is observable.
+    pub registry_heartbeat_interval: Duration,
 }
 
 impl Default for CheckpointFactoryOptions {
@@ -40,6 +62,10 @@ impl Default for CheckpointFactoryOptions {
         Self {
             storage_config: DataStorage::Local(()),
             persist_queue_capacity: 100,
+            restore_concurrency: 16,
+            compaction_slice_threshold: 32,
+            registry_database_url: None,
+            registry_heartbeat_interval: Duration::from_secs(30),
         }
     }
 }
@@ -109,7 +135,8 @@ impl CheckpointFactory {
     ) -> Result<(Self, OptionCheckpoint, JoinHandle<()>), ExecutionError> {
         let (storage, prefix) =
             create_data_storage(options.storage_config, checkpoint_dir.to_string()).await?;
-        let (record_store, checkpoint) = read_record_store_slices(&*storage, &prefix).await?;
+        let (record_store, checkpoint) =
+            read_record_store_slices(&*storage, &prefix, options.restore_concurrency).await?;
         if let Some(checkpoint) = &checkpoint.checkpoint {
             info!(
                 "Restored record store from {}th checkpoint, last epoch id is {}, processor states are stored in {}",
@@ -124,14 +151,63 @@ impl CheckpointFactory {
 
         let state = Mutex::new(CheckpointWriterFactoryState {
             next_record_index: record_store.num_records(),
+            num_slices: checkpoint.num_slices(),
+            compacting: false,
         });
 
+        // The registry is an optional, best-effort observability layer: a failure to
+        // connect disables it for this run rather than failing checkpoint restore.
+        let registry = match &options.registry_database_url {
+            Some(database_url) => match CheckpointRegistry::connect(database_url).await {
+                // The connection driver task is left detached; it runs for as long as
+                // the tokio runtime does, independent of this handle.
+                Ok((registry, _connection_driver)) => Some(Arc::new(registry)),
+                Err(e) => {
+                    warn!("Failed to connect to checkpoint registry: {e:?}");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if let Some(registry) = &registry {
+            match registry.latest_committed(&prefix).await {
+                Ok(Some(committed)) => {
+                    let storage_epoch = checkpoint.checkpoint.as_ref().map(|c| c.epoch_id);
+                    if storage_epoch != Some(committed.epoch_id) {
+                        warn!(
+                            "Checkpoint registry's latest committed epoch ({}) disagrees with \
+                             what storage discovered ({:?}); trusting storage as the source of truth",
+                            committed.epoch_id, storage_epoch
+                        );
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read latest committed checkpoint: {e:?}"),
+            }
+
+            let registry = Arc::clone(registry);
+            let heartbeat_prefix = prefix.clone();
+            let heartbeat_interval = options.registry_heartbeat_interval;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(heartbeat_interval);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = registry.heartbeat(&heartbeat_prefix).await {
+                        warn!("Failed to send checkpoint registry heartbeat: {e:?}");
+                    }
+                }
+            });
+        }
+
         Ok((
             Self {
                 queue,
                 storage,
                 prefix,
                 record_store: Arc::new(record_store),
+                compaction_slice_threshold: options.compaction_slice_threshold,
+                registry,
                 state,
             },
             checkpoint,
@@ -139,6 +215,19 @@ impl CheckpointFactory {
         ))
     }
 
+    /// Reads the latest committed epoch id from the checkpoint registry, if one is
+    /// configured, instead of re-listing storage.
+    pub async fn latest_committed_epoch(&self) -> Option<u64> {
+        let registry = self.registry.as_ref()?;
+        match registry.latest_committed(&self.prefix).await {
+            Ok(committed) => committed.map(|c| c.epoch_id),
+            Err(e) => {
+                warn!("Failed to read latest committed checkpoint: {e:?}");
+                None
+            }
+        }
+    }
+
     pub fn storage(&self) -> &dyn Storage {
         &*self.storage
     }
@@ -151,19 +240,141 @@ impl CheckpointFactory {
         &self.record_store
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn write_record_store_slice(
-        &self,
+        factory: &Arc<Self>,
+        epoch_id: u64,
         key: String,
+        processor_prefix: String,
         source_states: &SourceStates,
     ) -> Result<(), ExecutionError> {
-        let mut state = self.state.lock();
-        let (data, num_records_serialized) =
-            self.record_store.serialize_slice(state.next_record_index)?;
+        let mut state = factory.state.lock();
+        let (data, num_records_serialized) = factory
+            .record_store
+            .serialize_slice(state.next_record_index)?;
         state.next_record_index += num_records_serialized;
+        state.num_slices += 1;
+        let num_slices = state.num_slices;
+        let should_compact = !state.compacting && num_slices > factory.compaction_slice_threshold;
+        if should_compact {
+            state.compacting = true;
+        }
         drop(state);
 
-        self.write_record_store_slice_data(key, source_states, data)
-            .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)
+        // Spawn the registry's `writing` mark before the slice data upload below is
+        // enqueued, and only upsert the row as committed once that enqueue succeeded.
+        // If the enqueue fails, `upload_enqueued_tx` is dropped unsent and the row
+        // stays `writing`.
+        let (upload_enqueued_tx, upload_enqueued_rx) = tokio::sync::oneshot::channel();
+        factory.spawn_registry_mark_writing_then_committed(
+            epoch_id,
+            key.clone(),
+            processor_prefix,
+            source_states.clone(),
+            num_slices,
+            upload_enqueued_rx,
+        );
+
+        factory
+            .write_record_store_slice_data(key, source_states, data)
+            .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)?;
+        // The receiver is gone when no registry task was spawned; nothing to signal then.
+        let _ = upload_enqueued_tx.send(());
+
+        if should_compact {
+            factory.spawn_compaction();
+        }
+
+        Ok(())
+    }
+
+    /// Marks `epoch_id` as `writing` and then, once `upload_enqueued` fires, upserts it
+    /// as `committed` in a single spawned task, so the two writes to the same
+    /// `(prefix, epoch_id)` row are
+    /// strictly ordered. Spawning them as two independent tasks would give no
+    /// guarantee which one's `execute()` call reaches the registry first, so a
+    /// `committed` row could be overwritten back to `writing` if they raced.
+    /// Best-effort and non-blocking: a no-op if no tokio runtime is currently
+    /// entered (e.g. `CheckpointWriter` is dropped outside a tokio context in
+    /// tests), and failures are only logged since the object store, not the
+    /// registry, is the source of truth.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_registry_mark_writing_then_committed(
+        self: &Arc<Self>,
+        epoch_id: u64,
+        record_store_key: String,
+        processor_prefix: String,
+        source_states: SourceStates,
+        num_slices: usize,
+        upload_enqueued: tokio::sync::oneshot::Receiver<()>,
+    ) {
+        let Some(registry) = self.registry.clone() else {
+            return;
+        };
+        let Ok(handle) = dozer_log::tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let prefix = self.prefix.clone();
+        handle.spawn(async move {
+            if let Err(e) = registry.mark_writing(&prefix, epoch_id).await {
+                warn!("Failed to mark checkpoint registry row as writing: {e:?}");
+            }
+            if upload_enqueued.await.is_err() {
+                // The slice data upload was never enqueued; leave the row as `writing`.
+                return;
+            }
+            if let Err(e) = registry
+                .upsert_committed(
+                    &prefix,
+                    epoch_id,
+                    &record_store_key,
+                    &processor_prefix,
+                    &source_states,
+                    num_slices,
+                )
+                .await
+            {
+                warn!("Failed to upsert checkpoint registry row: {e:?}");
+            }
+        });
+    }
+
+    /// Spawns a background task that consolidates all `record_store/` slices into a
+    /// single object keyed by the latest epoch id, bounding restore cost. A no-op if
+    /// no tokio runtime is currently entered (e.g. `CheckpointWriter` is dropped
+    /// outside a tokio context in tests) — compaction is simply retried on the next
+    /// slice write.
+    fn spawn_compaction(self: &Arc<Self>) {
+        let Ok(handle) = dozer_log::tokio::runtime::Handle::try_current() else {
+            self.state.lock().compacting = false;
+            return;
+        };
+
+        let factory = Arc::clone(self);
+        handle.spawn(async move {
+            let storage = dyn_clone::clone_box(&*factory.storage);
+            let result = compact_record_store_slices(
+                &*storage,
+                factory.queue.clone(),
+                factory.prefix.clone(),
+            )
+            .await;
+
+            let mut state = factory.state.lock();
+            state.compacting = false;
+            match result {
+                Ok(0) => {}
+                Ok(merged_count) => {
+                    info!("Compacted checkpoint record store slices into one object");
+                    // `merged_count` slices collapsed into 1, so the counter drops by
+                    // `merged_count - 1` rather than being reset to 1 outright, which
+                    // would discard slices written concurrently while compaction ran.
+                    state.num_slices = state.num_slices.saturating_sub(merged_count - 1);
+                }
+                Err(e) => error!("Failed to compact checkpoint record store slices: {e:?}"),
+            }
+        });
     }
 
     fn write_record_store_slice_data(
@@ -171,18 +382,30 @@ impl CheckpointFactory {
         key: String,
         source_states: &SourceStates,
         data: Vec<u8>,
+    ) -> Result<(), SendError<String>> {
+        Self::write_record_store_slice_data_with(&self.queue, key, source_states, data)
+    }
+
+    /// Same as [`Self::write_record_store_slice_data`], but against an explicit
+    /// `Queue` so it can also be used to upload a consolidated object from the
+    /// compaction task, which only holds a cloned `Queue`, not a `CheckpointFactory`.
+    fn write_record_store_slice_data_with(
+        queue: &Queue,
+        key: String,
+        source_states: &SourceStates,
+        data: Vec<u8>,
     ) -> Result<(), SendError<String>> {
         let source_states =
             bincode::serialize(source_states).expect("Source states should be serializable");
 
-        self.queue.create_upload(key.clone())?;
-        self.queue.upload_chunk(
+        queue.create_upload(key.clone())?;
+        queue.upload_chunk(
             key.clone(),
             (source_states.len() as u64).to_le_bytes().to_vec(),
         )?;
-        self.queue.upload_chunk(key.clone(), source_states)?;
-        self.queue.upload_chunk(key.clone(), data)?;
-        self.queue.complete_upload(key)?;
+        queue.upload_chunk(key.clone(), source_states)?;
+        queue.upload_chunk(key.clone(), data)?;
+        queue.complete_upload(key)?;
         Ok(())
     }
 
@@ -214,11 +437,14 @@ impl CheckpointFactory {
 #[derive(Debug)]
 struct CheckpointWriterFactoryState {
     next_record_index: usize,
+    num_slices: usize,
+    compacting: bool,
 }
 
 #[derive(Debug)]
 pub struct CheckpointWriter {
     factory: Arc<CheckpointFactory>,
+    epoch_id: u64,
     record_store_key: String,
     source_states: Arc<SourceStates>,
     processor_prefix: String,
@@ -247,13 +473,14 @@ impl CheckpointWriter {
         source_states: Arc<SourceStates>,
     ) -> Self {
         // Format with `u64` max number of digits.
-        let epoch_id = format!("{:020}", epoch_id);
+        let padded_epoch_id = format!("{:020}", epoch_id);
         let record_store_key = record_store_prefix(&factory.prefix)
-            .join(&epoch_id)
+            .join(&padded_epoch_id)
             .into_string();
-        let processor_prefix = processor_prefix(&factory.prefix, &epoch_id);
+        let processor_prefix = processor_prefix(&factory.prefix, &padded_epoch_id);
         Self {
             factory,
+            epoch_id,
             record_store_key,
             source_states,
             processor_prefix,
@@ -274,8 +501,11 @@ impl CheckpointWriter {
     }
 
     fn drop(&mut self) -> Result<(), ExecutionError> {
-        self.factory.write_record_store_slice(
+        CheckpointFactory::write_record_store_slice(
+            &self.factory,
+            self.epoch_id,
             std::mem::take(&mut self.record_store_key),
+            std::mem::take(&mut self.processor_prefix),
             &self.source_states,
         )
     }
@@ -289,16 +519,44 @@ impl Drop for CheckpointWriter {
     }
 }
 
+/// Drains the oldest in-flight download and extends `record_store` with its data.
+///
+/// `downloads` is a [`FuturesOrdered`], so this always yields slices in the order they
+/// were pushed (i.e. ascending epoch id) even though the underlying downloads may
+/// complete out of order.
+async fn extend_from_next_download(
+    downloads: &mut FuturesOrdered<
+        impl std::future::Future<Output = Result<Vec<u8>, storage::Error>>,
+    >,
+    record_store: &ProcessorRecordStore,
+) -> Result<(), ExecutionError> {
+    if let Some(data) = downloads.next().await {
+        let data = data?;
+        let (_, data) = CheckpointFactory::read_record_store_slice_data(&data)?;
+        record_store.deserialize_and_extend(data)?;
+    }
+    Ok(())
+}
+
 async fn read_record_store_slices(
     storage: &dyn Storage,
     factory_prefix: &str,
+    restore_concurrency: usize,
 ) -> Result<(ProcessorRecordStore, OptionCheckpoint), ExecutionError> {
     let record_store = ProcessorRecordStore::new()?;
     let record_store_prefix = record_store_prefix(factory_prefix);
+    let restore_concurrency = restore_concurrency.max(1);
 
     let mut last_checkpoint: Option<Checkpoint> = None;
     let mut continuation_token = None;
+    // Bounded pipeline of in-flight downloads. Slices must be extended into the record
+    // store in ascending epoch-id order, so we rely on `FuturesOrdered` to buffer any
+    // out-of-order completions and drain them in submission order.
+    let mut downloads = FuturesOrdered::new();
     loop {
+        // The next page is listed without first draining the downloads queued from
+        // earlier pages. Queued downloads are only polled while one is being drained
+        // below, not while this listing request is awaited.
         let objects = storage
             .list_objects(record_store_prefix.to_string(), continuation_token)
             .await?;
@@ -337,10 +595,11 @@ async fn read_record_store_slices(
         }
 
         for object in objects.objects {
+            if downloads.len() >= restore_concurrency {
+                extend_from_next_download(&mut downloads, &record_store).await?;
+            }
             info!("Downloading {}", object.key);
-            let data = storage.download_object(object.key).await?;
-            let (_, data) = CheckpointFactory::read_record_store_slice_data(&data)?;
-            record_store.deserialize_and_extend(data)?;
+            downloads.push_back(storage.download_object(object.key));
         }
 
         continuation_token = objects.continuation_token;
@@ -349,6 +608,10 @@ async fn read_record_store_slices(
         }
     }
 
+    while !downloads.is_empty() {
+        extend_from_next_download(&mut downloads, &record_store).await?;
+    }
+
     Ok((
         record_store,
         OptionCheckpoint {
@@ -357,6 +620,124 @@ async fn read_record_store_slices(
     ))
 }
 
+/// Merges every object under `record_store/` into a single consolidated object keyed
+/// with the latest epoch id, so `num_slices` collapses back to 1 on the next restore.
+///
+/// The consolidated object uses the exact same key as the current last slice and the
+/// same `[len][source_states][data]` framing as a regular slice, so
+/// `read_record_store_slices` doesn't need to special-case it. Superseded slices are
+/// only deleted after the consolidated object is confirmed to hold the merged
+/// content, so a crash mid-way leaves every original slice intact and compaction
+/// simply retries later.
+///
+/// Returns the number of slices merged (0 if there was nothing to compact), so the
+/// caller can adjust its slice counter by how many slices actually disappeared
+/// instead of assuming the result always collapses to exactly one.
+async fn compact_record_store_slices(
+    storage: &dyn Storage,
+    queue: Queue,
+    factory_prefix: String,
+) -> Result<usize, ExecutionError> {
+    let record_store_prefix = record_store_prefix(&factory_prefix);
+
+    let mut objects = Vec::new();
+    let mut continuation_token = None;
+    loop {
+        let page = storage
+            .list_objects(record_store_prefix.to_string(), continuation_token)
+            .await?;
+        objects.extend(page.objects);
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    if objects.len() <= 1 {
+        // Nothing to compact.
+        return Ok(0);
+    }
+
+    let merged_store = ProcessorRecordStore::new()?;
+    let mut source_states = None;
+    for object in &objects {
+        let data = storage.download_object(object.key.clone()).await?;
+        let (states, data) = CheckpointFactory::read_record_store_slice_data(&data)?;
+        merged_store.deserialize_and_extend(data)?;
+        source_states = Some(states);
+    }
+    let source_states = source_states.expect("objects is non-empty, checked above");
+    let consolidated_key = objects
+        .last()
+        .expect("objects is non-empty, checked above")
+        .key
+        .clone();
+
+    let (data, _) = merged_store.serialize_slice(0)?;
+
+    // `consolidated_key` is reused from the last pre-merge slice, so it already holds
+    // *valid* `[len][source_states][data]` framing before this upload -- checking that
+    // `download_object` merely parses isn't enough to tell "merged" from "not merged
+    // yet". Build the exact bytes we expect the upload to produce so
+    // `await_consolidated_upload` can confirm the merged content specifically landed.
+    let bincoded_source_states =
+        bincode::serialize(&source_states).expect("Source states should be serializable");
+    let mut expected_object_bytes =
+        Vec::with_capacity(8 + bincoded_source_states.len() + data.len());
+    expected_object_bytes.extend_from_slice(&(bincoded_source_states.len() as u64).to_le_bytes());
+    expected_object_bytes.extend_from_slice(&bincoded_source_states);
+    expected_object_bytes.extend_from_slice(&data);
+
+    CheckpointFactory::write_record_store_slice_data_with(
+        &queue,
+        consolidated_key.clone(),
+        &source_states,
+        data,
+    )
+    .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)?;
+
+    // `complete_upload` above only confirms the write was *enqueued* on `Queue`'s
+    // background worker, not that it's durably written. Deleting superseded slices
+    // based on that alone could lose every slice if the queued upload later fails or
+    // the process crashes before it's flushed. So wait until the consolidated object
+    // is actually readable back from storage before deleting anything.
+    await_consolidated_upload(storage, &consolidated_key, &expected_object_bytes).await?;
+
+    let merged_count = objects.len();
+    for object in objects {
+        if object.key != consolidated_key {
+            storage.delete_object(object.key).await?;
+        }
+    }
+
+    Ok(merged_count)
+}
+
+/// Polls storage until `key` reads back as exactly `expected`, confirming the
+/// consolidated object from [`compact_record_store_slices`] actually landed before
+/// its superseded slices are deleted.
+async fn await_consolidated_upload(
+    storage: &dyn Storage,
+    key: &str,
+    expected: &[u8],
+) -> Result<(), ExecutionError> {
+    const MAX_ATTEMPTS: u32 = 30;
+    const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        if let Ok(data) = storage.download_object(key.to_string()).await {
+            if data == expected {
+                return Ok(());
+            }
+        }
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+
+    Err(ExecutionError::UnrecognizedCheckpoint(key.to_string()))
+}
+
 /// This is only meant to be used in tests.
 pub async fn create_checkpoint_factory_for_test(
     records: &[Vec<Field>],
@@ -418,4 +799,78 @@ mod tests {
     async fn checkpoint_writer_should_write_records() {
         create_checkpoint_factory_for_test(&[vec![Field::Int(0)]]).await;
     }
+
+    /// Regression test for compaction's delete-ordering: slices must survive until the
+    /// consolidated object is confirmed durable, so restoring after compaction must
+    /// never lose records even though every superseded slice has been deleted.
+    #[tokio::test]
+    async fn compaction_preserves_all_records_across_restore() {
+        let temp_dir = TempDir::new("compaction_preserves_all_records_across_restore").unwrap();
+        let checkpoint_dir = temp_dir.path().to_str().unwrap().to_string();
+
+        let options = CheckpointFactoryOptions {
+            compaction_slice_threshold: 1,
+            ..Default::default()
+        };
+        let (checkpoint_factory, _, worker) =
+            CheckpointFactory::new(checkpoint_dir.clone(), options)
+                .await
+                .unwrap();
+        let factory = Arc::new(checkpoint_factory);
+        let runtime_handle = tokio::runtime::Handle::current();
+
+        let records: Vec<Vec<Field>> = (0..3).map(|i| vec![Field::Int(i)]).collect();
+        for (epoch_id, record) in records.iter().enumerate() {
+            factory.record_store().create_ref(record).unwrap();
+            let source_states: Arc<SourceStates> = Arc::new(
+                [(
+                    NodeHandle::new(Some(1), "id".to_string()),
+                    OpIdentifier::new(epoch_id as u64, 1),
+                )]
+                .into_iter()
+                .collect(),
+            );
+            let factory = Arc::clone(&factory);
+            let runtime_handle = runtime_handle.clone();
+            let epoch_id = epoch_id as u64;
+            // Dropped on a plain OS thread, same as `create_checkpoint_factory_for_test`,
+            // so the writer's blocking queue sends can't deadlock this current-thread test
+            // runtime; entering the handle first still lets `spawn_compaction`'s
+            // `Handle::try_current()` find a runtime to actually spawn compaction onto.
+            std::thread::spawn(move || {
+                let _guard = runtime_handle.enter();
+                drop(CheckpointWriter::new(factory, epoch_id, source_states));
+            })
+            .join()
+            .unwrap();
+        }
+
+        // Compaction runs in the background once the threshold is exceeded; wait for it.
+        for _ in 0..100 {
+            if !factory.state.lock().compacting {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(
+            !factory.state.lock().compacting,
+            "compaction never finished"
+        );
+        assert_eq!(factory.state.lock().num_slices, 1);
+
+        drop(factory);
+        worker.await.unwrap();
+
+        // If slices had been deleted before the consolidated object was confirmed
+        // durable, restoring here would lose records instead of seeing all of them
+        // collapsed into a single slice.
+        let (reopened_factory, last_checkpoint, worker) =
+            CheckpointFactory::new(checkpoint_dir, Default::default())
+                .await
+                .unwrap();
+        let last_checkpoint = last_checkpoint.checkpoint.unwrap();
+        assert_eq!(last_checkpoint.num_slices.get(), 1);
+        assert_eq!(reopened_factory.record_store().num_records(), records.len());
+        worker.await.unwrap();
+    }
 }
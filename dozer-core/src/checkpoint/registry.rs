@@ -0,0 +1,215 @@
+//! Queryable Postgres-backed record of checkpoint progress.
+//!
+//! Object storage alone can't answer "which epoch is committed and when did it last
+//! advance" without listing every object under the checkpoint prefix. When
+//! configured, [`CheckpointRegistry`] keeps a `checkpoints` table alongside storage
+//! that mirrors the latest committed epoch and is touched by a periodic heartbeat,
+//! so a stalled pipeline -- or a half-written checkpoint -- is directly observable.
+
+use dozer_log::tokio;
+use dozer_types::{
+    log::error,
+    node::{NodeHandle, OpIdentifier, SourceStates},
+    serde_json,
+    thiserror::{self, Error},
+};
+use tokio_postgres::NoTls;
+
+/// `SourceStates` is keyed by `NodeHandle`, which isn't a string or number, so
+/// `serde_json` can't serialize it directly as a JSON object's keys -- it only
+/// accepts string keys. Going through a list of pairs instead sidesteps that
+/// restriction entirely, the same way bincode already does for the object-storage
+/// framing.
+fn source_states_to_json(source_states: &SourceStates) -> Result<serde_json::Value, RegistryError> {
+    let pairs: Vec<(&NodeHandle, &OpIdentifier)> = source_states.iter().collect();
+    Ok(serde_json::to_value(pairs)?)
+}
+
+fn source_states_from_json(value: serde_json::Value) -> Result<SourceStates, RegistryError> {
+    let pairs: Vec<(NodeHandle, OpIdentifier)> = serde_json::from_value(value)?;
+    Ok(pairs.into_iter().collect())
+}
+
+// Registry errors are never fatal to checkpointing: the object store remains the
+// source of truth, and the registry is an optional, best-effort observability
+// layer on top of it. Callers log `RegistryError`s rather than propagating them as
+// `ExecutionError`.
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("postgres error: {0}")]
+    Postgres(#[from] tokio_postgres::Error),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The latest committed row for a given checkpoint prefix.
+#[derive(Debug, Clone)]
+pub struct CommittedCheckpoint {
+    pub epoch_id: u64,
+    pub record_store_key: String,
+    pub processor_prefix: String,
+    pub source_states: SourceStates,
+    pub num_slices: usize,
+}
+
+#[derive(Debug)]
+pub struct CheckpointRegistry {
+    client: tokio_postgres::Client,
+}
+
+impl CheckpointRegistry {
+    /// Connects to `database_url`, creates the `checkpoints` table if it doesn't
+    /// exist yet, and returns the registry together with the join handle for its
+    /// connection driver task (dropping the handle detaches the task; it keeps
+    /// running).
+    pub async fn connect(
+        database_url: &str,
+    ) -> Result<(Self, tokio::task::JoinHandle<()>), RegistryError> {
+        let (client, connection) = tokio_postgres::connect(database_url, NoTls).await?;
+        let handle = tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!("Checkpoint registry connection closed: {e:?}");
+            }
+        });
+
+        let registry = Self { client };
+        registry.ensure_schema().await?;
+        Ok((registry, handle))
+    }
+
+    async fn ensure_schema(&self) -> Result<(), RegistryError> {
+        self.client
+            .batch_execute(
+                "DO $$ BEGIN
+                     CREATE TYPE checkpoint_status AS ENUM ('writing', 'committed');
+                 EXCEPTION WHEN duplicate_object THEN NULL;
+                 END $$;
+
+                 CREATE TABLE IF NOT EXISTS checkpoints (
+                     prefix TEXT NOT NULL,
+                     epoch_id BIGINT NOT NULL,
+                     record_store_key TEXT NOT NULL,
+                     processor_prefix TEXT NOT NULL,
+                     source_states JSONB NOT NULL,
+                     num_slices BIGINT NOT NULL,
+                     status checkpoint_status NOT NULL,
+                     updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                     PRIMARY KEY (prefix, epoch_id)
+                 );",
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Marks `epoch_id` as currently being written. The row stays `writing` until
+    /// [`Self::upsert_committed`] is called for the same epoch.
+    pub async fn mark_writing(&self, prefix: &str, epoch_id: u64) -> Result<(), RegistryError> {
+        self.client
+            .execute(
+                "INSERT INTO checkpoints
+                     (prefix, epoch_id, record_store_key, processor_prefix, source_states, num_slices, status, updated_at)
+                 VALUES ($1, $2, '', '', '{}', 0, 'writing', now())
+                 ON CONFLICT (prefix, epoch_id) DO UPDATE SET
+                     status = 'writing',
+                     updated_at = now()",
+                &[&prefix, &(epoch_id as i64)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Upserts the row for `epoch_id` and marks it `committed`. `CheckpointFactory`
+    /// calls this once the epoch's slice data upload has been enqueued; it does not
+    /// wait for the upload to reach storage.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert_committed(
+        &self,
+        prefix: &str,
+        epoch_id: u64,
+        record_store_key: &str,
+        processor_prefix: &str,
+        source_states: &SourceStates,
+        num_slices: usize,
+    ) -> Result<(), RegistryError> {
+        let source_states = source_states_to_json(source_states)?;
+        self.client
+            .execute(
+                "INSERT INTO checkpoints
+                     (prefix, epoch_id, record_store_key, processor_prefix, source_states, num_slices, status, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, 'committed', now())
+                 ON CONFLICT (prefix, epoch_id) DO UPDATE SET
+                     record_store_key = excluded.record_store_key,
+                     processor_prefix = excluded.processor_prefix,
+                     source_states = excluded.source_states,
+                     num_slices = excluded.num_slices,
+                     status = 'committed',
+                     updated_at = now()",
+                &[
+                    &prefix,
+                    &(epoch_id as i64),
+                    &record_store_key,
+                    &processor_prefix,
+                    &source_states,
+                    &(num_slices as i64),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Touches `updated_at` on the latest row for `prefix`, even when no new epoch
+    /// has committed, so a stalled pipeline is observable as a stalled heartbeat
+    /// rather than silence.
+    pub async fn heartbeat(&self, prefix: &str) -> Result<(), RegistryError> {
+        self.client
+            .execute(
+                "UPDATE checkpoints SET updated_at = now()
+                 WHERE prefix = $1 AND epoch_id = (
+                     SELECT epoch_id FROM checkpoints
+                     WHERE prefix = $1
+                     ORDER BY epoch_id DESC
+                     LIMIT 1
+                 )",
+                &[&prefix],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reads the latest `committed` row for `prefix`. A `writing` row for a higher
+    /// epoch id than any committed one indicates a half-written checkpoint and is
+    /// skipped. Restoring a checkpoint reads storage and does not consult this row.
+    pub async fn latest_committed(
+        &self,
+        prefix: &str,
+    ) -> Result<Option<CommittedCheckpoint>, RegistryError> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT epoch_id, record_store_key, processor_prefix, source_states, num_slices
+                 FROM checkpoints
+                 WHERE prefix = $1 AND status = 'committed'
+                 ORDER BY epoch_id DESC
+                 LIMIT 1",
+                &[&prefix],
+            )
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let epoch_id: i64 = row.get(0);
+        let record_store_key: String = row.get(1);
+        let processor_prefix: String = row.get(2);
+        let source_states: serde_json::Value = row.get(3);
+        let num_slices: i64 = row.get(4);
+
+        Ok(Some(CommittedCheckpoint {
+            epoch_id: epoch_id as u64,
+            record_store_key,
+            processor_prefix,
+            source_states: source_states_from_json(source_states)?,
+            num_slices: num_slices as usize,
+        }))
+    }
+}
@@ -21,41 +21,150 @@ pub async fn get_client(app_config: Config) -> TestPostgresClient {
     TestPostgresClient::new(config).await
 }
 
-pub async fn create_slot(client_mut: &mut Client, slot_name: &str) -> PgLsn {
+/// Configures which SQLSTATEs [`retry_drop_active_slot`] treats as transient (in
+/// addition to the built-in `AdminShutdown`/`SerializationFailure` classes) and how
+/// many times [`create_slot`] retries before giving up, so environments that see
+/// other transient errors (e.g. a pooler-specific code) don't have to fork this
+/// logic.
+#[derive(Debug, Clone)]
+pub struct SlotRecoveryConfig {
+    pub extra_retryable_sql_states: Vec<String>,
+    pub max_attempts: u32,
+}
+
+impl Default for SlotRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            extra_retryable_sql_states: Vec::new(),
+            max_attempts: 3,
+        }
+    }
+}
+
+pub async fn create_slot(
+    client_mut: &mut Client,
+    slot_name: &str,
+    config: &SlotRecoveryConfig,
+) -> Result<PgLsn, PostgresError> {
     client_mut
         .simple_query("BEGIN READ ONLY ISOLATION LEVEL REPEATABLE READ;")
-        .await
-        .unwrap();
+        .await?;
 
-    let created_lsn = ReplicationSlotHelper::create_replication_slot(client_mut, slot_name)
-        .await
-        .unwrap()
-        .unwrap();
-    client_mut.simple_query("COMMIT;").await.unwrap();
+    let mut attempt = 0;
+    let created_lsn = loop {
+        match ReplicationSlotHelper::create_replication_slot(client_mut, slot_name).await {
+            Ok(lsn) => {
+                break lsn.expect("replication slot creation should return a consistent point LSN")
+            }
+            Err(e) => {
+                attempt += 1;
+                if attempt > config.max_attempts {
+                    return Err(e);
+                }
+                match retry_drop_active_slot(e, client_mut, slot_name, config).await? {
+                    SlotRecoveryAction::TerminatedAndDropped | SlotRecoveryAction::Retried => {}
+                    SlotRecoveryAction::NonRetryable(e) => return Err(e),
+                }
+            }
+        }
+    };
+    client_mut.simple_query("COMMIT;").await?;
 
-    PgLsn::from_str(&created_lsn).unwrap()
+    Ok(PgLsn::from_str(&created_lsn).expect("Postgres returns a valid LSN string"))
+}
+
+/// SQLSTATE codes we branch on when recovering from a failed replication slot
+/// operation, analogous to how other Postgres crates build a static code -> variant
+/// map instead of matching five-character literals inline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlState {
+    /// `55006`: object in use (the slot is still active).
+    ObjectInUse,
+    /// `57P01`: the server is shutting down.
+    AdminShutdown,
+    /// `40001`: could not serialize access due to concurrent update.
+    SerializationFailure,
+    /// One of `config`'s `extra_retryable_sql_states`.
+    ConfiguredRetryable,
+    /// Any SQLSTATE we don't special-case.
+    Other,
+}
+
+impl SqlState {
+    fn classify(code: &str, config: &SlotRecoveryConfig) -> Self {
+        match code {
+            "55006" => SqlState::ObjectInUse,
+            "57P01" => SqlState::AdminShutdown,
+            "40001" => SqlState::SerializationFailure,
+            _ if config
+                .extra_retryable_sql_states
+                .iter()
+                .any(|retryable| retryable == code) =>
+            {
+                SqlState::ConfiguredRetryable
+            }
+            _ => SqlState::Other,
+        }
+    }
+}
+
+/// What `retry_drop_active_slot` did in response to a failed operation, so callers
+/// can log and react instead of relying on `unwrap()`.
+#[derive(Debug)]
+pub enum SlotRecoveryAction {
+    /// The blocking backend was terminated and the slot was dropped; the caller can
+    /// retry the operation that originally failed.
+    TerminatedAndDropped,
+    /// The error is transient (e.g. admin shutdown, serialization failure); the
+    /// caller can retry the operation as-is.
+    Retried,
+    /// The error isn't one we know how to recover from. Carries the original error
+    /// back so the caller can still log or propagate it.
+    NonRetryable(PostgresError),
+}
+
+/// Looks up the backend PID currently holding `slot_name`, if any, by querying
+/// `pg_stat_activity` for the PID recorded against the slot in
+/// `pg_replication_slots` -- rather than parsing it out of an error message, which
+/// is fragile across Postgres locales and message wording.
+async fn blocking_backend_pid(
+    client_mut: &mut Client,
+    slot_name: &str,
+) -> Result<Option<i32>, PostgresError> {
+    let query = format!(
+        "select pid from pg_stat_activity where pid = \
+         (select active_pid from pg_replication_slots where slot_name = '{slot_name}');"
+    );
+    let messages = client_mut.simple_query(query.as_ref()).await?;
+    Ok(messages.into_iter().find_map(|message| match message {
+        SimpleQueryMessage::Row(row) => row.get(0).and_then(|pid| pid.parse().ok()),
+        _ => None,
+    }))
 }
 
 pub async fn retry_drop_active_slot(
     e: PostgresError,
     client_mut: &mut Client,
     slot_name: &str,
-) -> Result<Vec<SimpleQueryMessage>, PostgresError> {
-    match e.source() {
-        None => Err(e),
-        Some(err) => match err.downcast_ref::<DbError>() {
-            Some(db_error) if db_error.code().code().eq("55006") => {
-                let err = db_error.to_string();
-                let parts = err.rsplit_once(' ').unwrap();
+    config: &SlotRecoveryConfig,
+) -> Result<SlotRecoveryAction, PostgresError> {
+    let Some(db_error) = e.source().and_then(|err| err.downcast_ref::<DbError>()) else {
+        return Err(e);
+    };
 
+    match SqlState::classify(db_error.code().code(), config) {
+        SqlState::ObjectInUse => {
+            if let Some(pid) = blocking_backend_pid(client_mut, slot_name).await? {
                 client_mut
-                    .simple_query(format!("select pg_terminate_backend('{}');", parts.1).as_ref())
-                    .await
-                    .unwrap();
-
-                ReplicationSlotHelper::drop_replication_slot(client_mut, slot_name).await
+                    .simple_query(format!("select pg_terminate_backend({pid});").as_ref())
+                    .await?;
             }
-            _ => Err(e),
-        },
+            ReplicationSlotHelper::drop_replication_slot(client_mut, slot_name).await?;
+            Ok(SlotRecoveryAction::TerminatedAndDropped)
+        }
+        SqlState::AdminShutdown
+        | SqlState::SerializationFailure
+        | SqlState::ConfiguredRetryable => Ok(SlotRecoveryAction::Retried),
+        SqlState::Other => Ok(SlotRecoveryAction::NonRetryable(e)),
     }
 }
@@ -1,23 +1,126 @@
 use crate::pipeline::errors::PipelineError;
-use crate::pipeline::expression::execution::{
-    Expression, ExpressionExecutor, ExpressionType,
-};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{Field, FieldType, Record, Schema};
 
+/// The common type `left` and `right` are compared in: the type both can be
+/// losslessly coerced to where one exists, `Float` (lossy) for the numeric pairs
+/// that have none, and `None` if the two types are not comparable at all.
+///
+/// This isn't a simple total order: `UInt`/`U128` can't hold a negative `Int`/`I128`,
+/// and `I128`/`U128` can't hold each other's full range (sign vs. magnitude), so each
+/// pair is widened to whichever type the *combination* actually fits in losslessly,
+/// rather than always promoting to whichever side looks "bigger".
+fn common_super_type(left: FieldType, right: FieldType) -> Option<FieldType> {
+    use FieldType::*;
+
+    if left == right {
+        return Some(left);
+    }
+
+    match (left, right) {
+        (Date, Timestamp) | (Timestamp, Date) => Some(Timestamp),
+
+        // Every value of `Int`, `UInt` or `I128` fits losslessly in `I128`.
+        (Int, I128) | (I128, Int) => Some(I128),
+        (Int, UInt) | (UInt, Int) => Some(I128),
+        (I128, UInt) | (UInt, I128) => Some(I128),
+
+        // `U128` only ever has a lossless common type with another unsigned type.
+        (UInt, U128) | (U128, UInt) => Some(U128),
+
+        // `Decimal` covers the full range of `Int`/`UInt`, but not `I128`/`U128`.
+        (Int, Decimal) | (Decimal, Int) => Some(Decimal),
+        (UInt, Decimal) | (Decimal, UInt) => Some(Decimal),
+
+        // No lossless common type exists for these combinations (signed vs.
+        // oversized unsigned, the two 128-bit types against each other, or either
+        // against `Decimal`'s narrower range) or one side is already `Float`; fall
+        // back to `Float` so the comparison can still happen, lossily.
+        (Int | UInt | I128 | U128 | Decimal, Float)
+        | (Float, Int | UInt | I128 | U128 | Decimal) => Some(Float),
+        (Int, U128) | (U128, Int) => Some(Float),
+        (I128, U128) | (U128, I128) => Some(Float),
+        (I128, Decimal) | (Decimal, I128) => Some(Float),
+        (U128, Decimal) | (Decimal, U128) => Some(Float),
+
+        _ => None,
+    }
+}
+
+fn invalid_coercion(field: &Field, from: FieldType, to: FieldType) -> PipelineError {
+    PipelineError::InvalidExpression(format!(
+        "Cannot coerce {field:?} of type {from:?} to {to:?} for IN list comparison"
+    ))
+}
+
+/// Coerces `field` (known to have type `from`) into `to`, assuming `to` was produced
+/// by [`common_super_type`]. The conversion is lossless except when `to` is `Float`,
+/// where large integers and decimals are rounded.
+fn coerce_field(field: Field, from: FieldType, to: FieldType) -> Result<Field, PipelineError> {
+    if from == to || matches!(field, Field::Null) {
+        return Ok(field);
+    }
+
+    let err = || invalid_coercion(&field, from, to);
+
+    match (field, to) {
+        (Field::Int(v), FieldType::I128) => Ok(Field::I128(v as i128)),
+        (Field::Int(v), FieldType::UInt) => u64::try_from(v).map(Field::UInt).map_err(|_| err()),
+        (Field::Int(v), FieldType::U128) => u128::try_from(v).map(Field::U128).map_err(|_| err()),
+        (Field::Int(v), FieldType::Decimal) => Ok(Field::Decimal(Decimal::from(v))),
+        (Field::Int(v), FieldType::Float) => Ok(Field::Float(OrderedFloat(v as f64))),
+
+        (Field::I128(v), FieldType::UInt) => u64::try_from(v).map(Field::UInt).map_err(|_| err()),
+        (Field::I128(v), FieldType::U128) => u128::try_from(v).map(Field::U128).map_err(|_| err()),
+        (Field::I128(v), FieldType::Decimal) => {
+            Decimal::try_from(v).map(Field::Decimal).map_err(|_| err())
+        }
+        (Field::I128(v), FieldType::Float) => Ok(Field::Float(OrderedFloat(v as f64))),
+
+        (Field::UInt(v), FieldType::I128) => Ok(Field::I128(v as i128)),
+        (Field::UInt(v), FieldType::U128) => Ok(Field::U128(v as u128)),
+        (Field::UInt(v), FieldType::Decimal) => Ok(Field::Decimal(Decimal::from(v))),
+        (Field::UInt(v), FieldType::Float) => Ok(Field::Float(OrderedFloat(v as f64))),
+
+        (Field::U128(v), FieldType::Decimal) => {
+            Decimal::try_from(v).map(Field::Decimal).map_err(|_| err())
+        }
+        (Field::U128(v), FieldType::Float) => Ok(Field::Float(OrderedFloat(v as f64))),
+
+        (Field::Decimal(v), FieldType::Float) => v
+            .to_string()
+            .parse::<f64>()
+            .map(|f| Field::Float(OrderedFloat(f)))
+            .map_err(|_| err()),
+
+        (Field::Date(v), FieldType::Timestamp) => Ok(Field::Timestamp(
+            v.and_hms_opt(0, 0, 0)
+                .expect("midnight is a valid time")
+                .and_utc()
+                .fixed_offset(),
+        )),
+
+        (field, _) => Err(invalid_coercion(&field, from, to)),
+    }
+}
+
 pub(crate) fn get_in_list_operator_type(
     arg: &Expression,
     list: &[Expression],
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
-    let return_type = arg.get_type(schema)?.return_type;
+    let arg_type = arg.get_type(schema)?.return_type;
+    let mut common_type = arg_type;
     for val in list {
         let val_type = val.get_type(schema)?.return_type;
-        if val_type != return_type {
-            return Err(PipelineError::InvalidExpression(format!(
-                "Expected list member to have type {return_type:?} but found {val_type:?} \
-                 Expected because left side of IN expression has type {return_type:?}"
-            )));
-        }
+        common_type = common_super_type(common_type, val_type).ok_or_else(|| {
+            PipelineError::InvalidExpression(format!(
+                "Expected list member to have type {common_type:?} but found {val_type:?} \
+                 Expected because left side of IN expression has type {arg_type:?}"
+            ))
+        })?;
     }
 
     Ok(ExpressionType::new(
@@ -37,18 +140,127 @@ pub(crate) fn evaluate_in_list(
     let arg_field = arg.evaluate(record, schema)?;
     let arg_type = arg.get_type(schema)?.return_type;
 
+    // Mirror `get_in_list_operator_type`'s planning-time pass to find the type every
+    // member will be compared in, then coerce both sides into it before comparing.
+    let mut common_type = arg_type;
     for val in list {
-        let val_field = val.evaluate(record, schema)?;
         let val_type = val.get_type(schema)?.return_type;
-        if val_type != arg_type {
-            return Err(PipelineError::InvalidExpression(format!(
-                "Expected list member to have type {arg_type:?} but found {val_type:?} \
+        common_type = common_super_type(common_type, val_type).ok_or_else(|| {
+            PipelineError::InvalidExpression(format!(
+                "Expected list member to have type {common_type:?} but found {val_type:?} \
                  Expected because left side of IN expression has type {arg_type:?}"
-            )));
-        }
+            ))
+        })?;
+    }
+
+    let arg_field = coerce_field(arg_field, arg_type, common_type)?;
+
+    for val in list {
+        let val_field = val.evaluate(record, schema)?;
+        let val_type = val.get_type(schema)?.return_type;
+        let val_field = coerce_field(val_field, val_type, common_type)?;
         if arg_field == val_field {
             return Ok(Field::Boolean(true));
         }
     }
     Ok(Field::Boolean(false))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_super_type_resolves_compatible_numeric_and_temporal_pairs() {
+        use FieldType::*;
+
+        let cases = [
+            (Int, Int, Some(Int)),
+            (Date, Timestamp, Some(Timestamp)),
+            (Int, I128, Some(I128)),
+            (Int, UInt, Some(I128)),
+            (I128, UInt, Some(I128)),
+            (UInt, U128, Some(U128)),
+            (Int, Decimal, Some(Decimal)),
+            (UInt, Decimal, Some(Decimal)),
+            (Int, Float, Some(Float)),
+            (Int, U128, Some(Float)),
+            (I128, U128, Some(Float)),
+            (I128, Decimal, Some(Float)),
+            (U128, Decimal, Some(Float)),
+            (Int, Boolean, None),
+        ];
+
+        for (left, right, expected) in cases {
+            assert_eq!(
+                common_super_type(left, right),
+                expected,
+                "{left:?} vs {right:?}"
+            );
+            assert_eq!(
+                common_super_type(right, left),
+                expected,
+                "{right:?} vs {left:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn negative_int_against_uint_list_member_compares_false_instead_of_erroring() {
+        // `int_col IN (<uint literal>)` where the row's value is negative must
+        // evaluate to `false`, not fail: I128 holds every Int and every UInt value,
+        // so both sides coerce cleanly and simply compare unequal.
+        let common_type = common_super_type(FieldType::Int, FieldType::UInt).unwrap();
+        assert_eq!(common_type, FieldType::I128);
+
+        let arg = coerce_field(Field::Int(-5), FieldType::Int, common_type).unwrap();
+        let val = coerce_field(Field::UInt(5), FieldType::UInt, common_type).unwrap();
+        assert_ne!(arg, val);
+    }
+
+    #[test]
+    fn out_of_range_i128_against_u128_and_decimal_coerces_via_float() {
+        let huge = i128::MAX;
+
+        let common_with_u128 = common_super_type(FieldType::I128, FieldType::U128).unwrap();
+        assert_eq!(common_with_u128, FieldType::Float);
+        coerce_field(Field::I128(huge), FieldType::I128, common_with_u128).unwrap();
+        coerce_field(Field::U128(u128::MAX), FieldType::U128, common_with_u128).unwrap();
+
+        let common_with_decimal = common_super_type(FieldType::I128, FieldType::Decimal).unwrap();
+        assert_eq!(common_with_decimal, FieldType::Float);
+        coerce_field(Field::I128(huge), FieldType::I128, common_with_decimal).unwrap();
+    }
+
+    #[test]
+    fn decimal_col_in_int_list_compares_equal_via_decimal() {
+        // `decimal_col IN (1, 2)`: both list members coerce up to `Decimal`.
+        let common_type = common_super_type(FieldType::Decimal, FieldType::Int).unwrap();
+        assert_eq!(common_type, FieldType::Decimal);
+
+        let arg = coerce_field(
+            Field::Decimal(Decimal::from(2)),
+            FieldType::Decimal,
+            common_type,
+        )
+        .unwrap();
+        let val = coerce_field(Field::Int(2), FieldType::Int, common_type).unwrap();
+        assert_eq!(arg, val);
+    }
+
+    #[test]
+    fn int_col_in_mixed_int_and_float_list_compares_equal_via_float() {
+        // `int_col IN (1, 2.0)`.
+        let common_type = common_super_type(FieldType::Int, FieldType::Float).unwrap();
+        assert_eq!(common_type, FieldType::Float);
+
+        let arg = coerce_field(Field::Int(2), FieldType::Int, common_type).unwrap();
+        let val = coerce_field(
+            Field::Float(OrderedFloat(2.0)),
+            FieldType::Float,
+            common_type,
+        )
+        .unwrap();
+        assert_eq!(arg, val);
+    }
+}